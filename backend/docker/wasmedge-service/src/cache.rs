@@ -0,0 +1,90 @@
+// Content-addressable cache for compiled WASM modules.
+//
+// Keyed by the SHA-256 of the decoded WASM bytes so that a given module is
+// loaded and validated once and then reused by every later request that
+// references it. Hits skip straight to instantiation. Lookup and insert are
+// separate steps: two requests that miss on the same hash at the same time
+// each compile the module, and the later insert replaces the earlier one.
+
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use wasmedge_sdk::Module;
+
+pub type ModuleHash = [u8; 32];
+
+#[derive(Clone)]
+pub struct CachedModule {
+    pub module: Arc<Module>,
+}
+
+#[derive(Default)]
+pub struct ModuleCache {
+    modules: dashmap::DashMap<ModuleHash, CachedModule>,
+}
+
+impl ModuleCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn hash(bytes: &[u8]) -> ModuleHash {
+        let mut hasher = Sha256::new();
+        hasher.update(bytes);
+        hasher.finalize().into()
+    }
+
+    pub fn get(&self, hash: &ModuleHash) -> Option<CachedModule> {
+        self.modules.get(hash).map(|entry| entry.clone())
+    }
+
+    pub fn insert(&self, hash: ModuleHash, module: CachedModule) {
+        self.modules.insert(hash, module);
+    }
+}
+
+/// Parses a lowercase/uppercase hex-encoded SHA-256 digest, e.g. one supplied
+/// by a caller via `ExecuteRequest.wasm_sha256`.
+pub fn parse_hash_hex(s: &str) -> Option<ModuleHash> {
+    if s.len() != 64 {
+        return None;
+    }
+    let mut out = [0u8; 32];
+    for (i, chunk) in s.as_bytes().chunks(2).enumerate() {
+        let byte_str = std::str::from_utf8(chunk).ok()?;
+        out[i] = u8::from_str_radix(byte_str, 16).ok()?;
+    }
+    Some(out)
+}
+
+pub fn hash_to_hex(hash: &ModuleHash) -> String {
+    hash.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_to_hex_round_trips_through_parse_hash_hex() {
+        let hash = ModuleCache::hash(b"wasm bytes");
+        let hex = hash_to_hex(&hash);
+        assert_eq!(parse_hash_hex(&hex), Some(hash));
+    }
+
+    #[test]
+    fn parse_hash_hex_accepts_uppercase() {
+        let lower = "a".repeat(64);
+        let upper = "A".repeat(64);
+        assert_eq!(parse_hash_hex(&lower), parse_hash_hex(&upper));
+    }
+
+    #[test]
+    fn parse_hash_hex_rejects_wrong_length() {
+        assert_eq!(parse_hash_hex("abcd"), None);
+    }
+
+    #[test]
+    fn parse_hash_hex_rejects_non_hex() {
+        assert_eq!(parse_hash_hex(&"zz".repeat(32)), None);
+    }
+}
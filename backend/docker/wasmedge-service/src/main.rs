@@ -3,21 +3,52 @@
 // HTTP service that wraps WasmEdge for executing WASM modules.
 // Accepts POST /execute with WASM binary and input, returns execution result.
 
+mod cache;
+#[cfg(feature = "wasi-nn")]
+mod nn;
+mod registry;
+mod wagi;
+
 use actix_web::{web, App, HttpServer, HttpResponse, Result as ActixResult};
+use cache::{CachedModule, ModuleCache, ModuleHash};
+use registry::{ManifestEntry, ManifestUpload, ModuleRegistry};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use wasmedge_sdk::{
-    config::{CommonConfigOptions, ConfigBuilder, HostRegistrationConfigOptions},
-    params, Vm, WasmValue,
+    config::{
+        CommonConfigOptions, ConfigBuilder, HostRegistrationConfigOptions, RuntimeConfigOptions,
+        StatisticsConfigOptions,
+    },
+    params, Module, Vm, VmBuilder, WasmValue,
 };
-use std::time::Instant;
+use std::time::{Duration, Instant};
+
+// Ceilings applied when a request doesn't set `memory_limit` / `timeout`.
+const DEFAULT_MEMORY_LIMIT_BYTES: u64 = 256 * 1024 * 1024; // 256 MiB
+const DEFAULT_TIMEOUT_MS: u64 = 30_000; // 30 s
+const WASM_PAGE_SIZE_BYTES: u64 = 64 * 1024; // WASM linear memory page size
 
 #[derive(Deserialize)]
 struct ExecuteRequest {
+    #[serde(default)]
     wasm: String, // Base64 encoded WASM binary
+    // SHA-256 (hex) of a module previously uploaded via `wasm`. When set and
+    // `wasm` is empty, the cached compiled module is reused instead.
+    wasm_sha256: Option<String>,
     input: serde_json::Value,
     function_name: Option<String>,
     memory_limit: Option<u64>,
     timeout: Option<u64>,
+    // Cost-metering ceiling; when set, the module traps with a distinct
+    // "gas exhausted" error once its accumulated cost exceeds it.
+    gas_limit: Option<u64>,
+    /// Selects an execution backend other than the default WASM `main`
+    /// invocation. Currently only `"wasi-nn"` is supported, and only when
+    /// this build was compiled with the `wasi-nn` feature. The module
+    /// itself still reads `input` from stdin and writes its result to
+    /// stdout as usual; this only registers the `wasi_nn` plugin so its
+    /// host function imports resolve.
+    backend: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -27,127 +58,501 @@ struct ExecuteResponse {
     error: Option<String>,
     execution_time: Option<u64>,
     memory_used: Option<u64>,
+    instructions_executed: Option<u64>,
+    cost: Option<u64>,
 }
 
-async fn execute_wasm(req: web::Json<ExecuteRequest>) -> ActixResult<HttpResponse> {
+impl ExecuteResponse {
+    /// A failure response carrying just an error message; the statistics
+    /// fields are left empty since they weren't collected (the module
+    /// never ran, or didn't run to completion).
+    fn error(execution_time: u64, message: impl Into<String>) -> Self {
+        ExecuteResponse {
+            success: false,
+            output: None,
+            error: Some(message.into()),
+            execution_time: Some(execution_time),
+            memory_used: None,
+            instructions_executed: None,
+            cost: None,
+        }
+    }
+}
+
+async fn execute_wasm(
+    req: web::Json<ExecuteRequest>,
+    cache: web::Data<ModuleCache>,
+) -> ActixResult<HttpResponse> {
     let start_time = Instant::now();
-    
-    // Decode WASM binary
-    let wasm_bytes = match base64::decode(&req.wasm) {
-        Ok(bytes) => bytes,
-        Err(e) => {
-            return Ok(HttpResponse::BadRequest().json(ExecuteResponse {
-                success: false,
-                output: None,
-                error: Some(format!("Invalid WASM base64: {}", e)),
-                execution_time: Some(start_time.elapsed().as_millis() as u64),
-                memory_used: None,
-            }));
+
+    // Resolve which module bytes (if any) we need to compile, and the hash
+    // that identifies them in the cache.
+    let wasm_bytes = if req.wasm.is_empty() {
+        None
+    } else {
+        match base64::decode(&req.wasm) {
+            Ok(bytes) => Some(bytes),
+            Err(e) => {
+                return Ok(HttpResponse::BadRequest().json(ExecuteResponse::error(start_time.elapsed().as_millis() as u64, format!("Invalid WASM base64: {}", e))));
+            }
+        }
+    };
+
+    let hash = match (&wasm_bytes, &req.wasm_sha256) {
+        (Some(bytes), _) => ModuleCache::hash(bytes),
+        (None, Some(hex)) => match cache::parse_hash_hex(hex) {
+            Some(hash) => hash,
+            None => {
+                return Ok(HttpResponse::BadRequest().json(ExecuteResponse::error(start_time.elapsed().as_millis() as u64, "wasm_sha256 must be a 64-character hex SHA-256 digest")));
+            }
+        },
+        (None, None) => {
+            return Ok(HttpResponse::BadRequest().json(ExecuteResponse::error(start_time.elapsed().as_millis() as u64, "request must set either `wasm` or `wasm_sha256`")));
         }
     };
 
-    // Create WasmEdge configuration
+    let function_name = req.function_name.as_deref().unwrap_or("main");
+    let memory_limit = req.memory_limit.unwrap_or(DEFAULT_MEMORY_LIMIT_BYTES);
+    let timeout_ms = req.timeout.unwrap_or(DEFAULT_TIMEOUT_MS);
+
+    let use_wasi_nn = match resolve_wasi_nn_backend(&req, start_time) {
+        Ok(flag) => flag,
+        Err(response) => return Ok(response),
+    };
+
+    Ok(run_module(
+        &cache,
+        hash,
+        wasm_bytes.as_deref(),
+        function_name,
+        &req.input,
+        memory_limit,
+        timeout_ms,
+        req.gas_limit,
+        use_wasi_nn,
+        start_time,
+    )
+    .await)
+}
+
+/// Resolves `req.backend` into whether the wasi-nn plugin should be
+/// registered on the VM. Returns `Ok(false)` for the default backend, and
+/// `Err(response)` with a ready-to-send error response when wasi-nn was
+/// requested but this build lacks the `wasi-nn` feature.
+fn resolve_wasi_nn_backend(req: &ExecuteRequest, start_time: Instant) -> Result<bool, HttpResponse> {
+    if req.backend.as_deref() != Some("wasi-nn") {
+        return Ok(false);
+    }
+
+    #[cfg(feature = "wasi-nn")]
+    {
+        Ok(true)
+    }
+    #[cfg(not(feature = "wasi-nn"))]
+    {
+        Err(HttpResponse::BadRequest().json(ExecuteResponse::error(
+            start_time.elapsed().as_millis() as u64,
+            "this build was not compiled with the `wasi-nn` feature",
+        )))
+    }
+}
+
+/// Builds the WasmEdge configuration shared by every VM and every freshly
+/// compiled `Module`, capping linear memory at `memory_limit_bytes` so a
+/// runaway module traps instead of exhausting host memory, and turning on
+/// instruction/cost statistics so `run_module` can report them afterwards.
+fn build_config(memory_limit_bytes: u64) -> Result<wasmedge_sdk::config::Config, String> {
+    let max_pages = (memory_limit_bytes / WASM_PAGE_SIZE_BYTES).clamp(1, u32::MAX as u64) as u32;
+
+    let host_options = HostRegistrationConfigOptions::default().wasi(true);
+
     let config = ConfigBuilder::new(CommonConfigOptions::default())
-        .with_host_registration_config(HostRegistrationConfigOptions::default().wasi(true))
+        .with_host_registration_config(host_options)
+        .with_runtime_config(RuntimeConfigOptions::default().max_memory_pages(max_pages))
+        .with_statistics_config(
+            StatisticsConfigOptions::default()
+                .count_instructions(true)
+                .measure_cost(true)
+                .measure_time(true),
+        )
         .build()
-        .map_err(|e| {
-            actix_web::error::ErrorInternalServerError(format!("Config error: {}", e))
-        })?;
+        .map_err(|e| format!("Config error: {}", e))?;
 
-    // Create VM
-    let vm = match Vm::new(Some(config)) {
-        Ok(vm) => vm,
+    Ok(config)
+}
+
+/// Statistics collected from a single `run_func` invocation.
+struct ExecutionStats {
+    memory_bytes: Option<u64>,
+    instructions_executed: Option<u64>,
+    cost: Option<u64>,
+}
+
+/// Reads back the peak linear memory size and the instruction/cost counters
+/// WasmEdge tracked for the run, if statistics were enabled on `Config`.
+fn collect_stats(vm: &Vm) -> ExecutionStats {
+    let (instructions_executed, cost) = match vm.statistics() {
+        Some(stats) => (Some(stats.count()), Some(stats.cost())),
+        None => (None, None),
+    };
+
+    // `Memory::size()` already returns the byte length, not a page count.
+    let memory_bytes = vm
+        .active_module()
+        .ok()
+        .and_then(|instance| instance.memory("memory").ok())
+        .map(|mem| mem.size() as u64);
+
+    ExecutionStats {
+        memory_bytes,
+        instructions_executed,
+        cost,
+    }
+}
+
+/// Resolves the compiled module for `hash` (compiling `wasm_bytes` on a
+/// cache miss, reusing the cached artifact on a hit), instantiates it, and
+/// runs `function_name` with a `timeout_ms` deadline and an optional
+/// `gas_limit` cost ceiling. `use_wasi_nn` registers the `wasi_nn` plugin on
+/// the VM when the request opted into that backend. Shared by `/execute`
+/// and `/run/{namespace}.{name}`.
+#[allow(clippy::too_many_arguments)]
+async fn run_module(
+    cache: &ModuleCache,
+    hash: ModuleHash,
+    wasm_bytes: Option<&[u8]>,
+    function_name: &str,
+    input: &serde_json::Value,
+    memory_limit_bytes: u64,
+    timeout_ms: u64,
+    gas_limit: Option<u64>,
+    #[cfg_attr(not(feature = "wasi-nn"), allow(unused_variables))] use_wasi_nn: bool,
+    start_time: Instant,
+) -> HttpResponse {
+    let config = match build_config(memory_limit_bytes) {
+        Ok(config) => config,
         Err(e) => {
-            return Ok(HttpResponse::InternalServerError().json(ExecuteResponse {
-                success: false,
-                output: None,
-                error: Some(format!("Failed to create VM: {}", e)),
-                execution_time: Some(start_time.elapsed().as_millis() as u64),
-                memory_used: None,
-            }));
+            return HttpResponse::InternalServerError().json(ExecuteResponse::error(start_time.elapsed().as_millis() as u64, e));
         }
     };
 
-    // Load WASM module
-    let vm = match vm.load_wasm_from_bytes(&wasm_bytes) {
-        Ok(vm) => vm,
-        Err(e) => {
-            return Ok(HttpResponse::BadRequest().json(ExecuteResponse {
-                success: false,
-                output: None,
-                error: Some(format!("Failed to load WASM: {}", e)),
-                execution_time: Some(start_time.elapsed().as_millis() as u64),
-                memory_used: None,
-            }));
+    // Resolve the compiled module, compiling (load + validate) on a cache
+    // miss and reusing the cached artifact on a hit.
+    let module = match cache.get(&hash) {
+        Some(cached) => cached.module,
+        None => {
+            let bytes = match wasm_bytes {
+                Some(bytes) => bytes,
+                None => {
+                    return HttpResponse::NotFound().json(ExecuteResponse::error(
+                        start_time.elapsed().as_millis() as u64,
+                        format!(
+                            "no cached module for hash {}; upload it via `wasm` first",
+                            cache::hash_to_hex(&hash)
+                        ),
+                    ));
+                }
+            };
+
+            let compiled = match Module::from_bytes(Some(&config), bytes) {
+                Ok(module) => module,
+                Err(e) => {
+                    return HttpResponse::BadRequest().json(ExecuteResponse::error(start_time.elapsed().as_millis() as u64, format!("Failed to load/validate WASM: {}", e)));
+                }
+            };
+
+            let module = Arc::new(compiled);
+            cache.insert(
+                hash,
+                CachedModule {
+                    module: module.clone(),
+                },
+            );
+            module
         }
     };
 
-    // Validate WASM module
-    let vm = match vm.validate() {
+    // Create VM. Requests on the wasi-nn backend build it through
+    // `VmBuilder::with_plugin_wasi_nn()` so the module's wasi-nn host
+    // function imports resolve; everything else uses a plain `VmBuilder`
+    // with no plugins. `Vm` itself has no standalone constructor — it's only
+    // ever produced by `VmBuilder::build()`.
+    #[cfg(feature = "wasi-nn")]
+    let vm_result: Result<Vm, String> = if use_wasi_nn {
+        nn::build_vm(config)
+    } else {
+        VmBuilder::new().with_config(config).build().map_err(|e| e.to_string())
+    };
+    #[cfg(not(feature = "wasi-nn"))]
+    let vm_result: Result<Vm, String> =
+        VmBuilder::new().with_config(config).build().map_err(|e| e.to_string());
+
+    let mut vm = match vm_result {
         Ok(vm) => vm,
         Err(e) => {
-            return Ok(HttpResponse::BadRequest().json(ExecuteResponse {
-                success: false,
-                output: None,
-                error: Some(format!("Invalid WASM module: {}", e)),
-                execution_time: Some(start_time.elapsed().as_millis() as u64),
-                memory_used: None,
-            }));
+            return HttpResponse::InternalServerError().json(ExecuteResponse::error(start_time.elapsed().as_millis() as u64, format!("Failed to create VM: {}", e)));
         }
     };
 
-    // Instantiate module
-    let vm = match vm.instantiate() {
+    // A gas limit traps the module once its accumulated cost exceeds the
+    // ceiling, rather than letting it run unmetered.
+    if let Some(limit) = gas_limit {
+        if let Some(stats) = vm.statistics_mut() {
+            stats.set_cost_limit(limit);
+        }
+    }
+
+    // Register the (possibly cached) compiled module as the VM's active,
+    // unnamed module. `register_module` both loads and instantiates it.
+    let mut vm = match vm.register_module(None, (*module).clone()) {
         Ok(vm) => vm,
         Err(e) => {
-            return Ok(HttpResponse::InternalServerError().json(ExecuteResponse {
-                success: false,
-                output: None,
-                error: Some(format!("Failed to instantiate WASM: {}", e)),
-                execution_time: Some(start_time.elapsed().as_millis() as u64),
-                memory_used: None,
-            }));
+            return HttpResponse::BadRequest().json(ExecuteResponse::error(start_time.elapsed().as_millis() as u64, format!("Failed to load WASM module: {}", e)));
         }
     };
 
-    // Prepare input
-    let input_json = serde_json::to_string(&req.input).unwrap_or_else(|_| "{}".to_string());
-    let function_name = req.function_name.as_deref().unwrap_or("main");
+    // Prepare input: serialized as the module's stdin, CGI-style, with a
+    // little request metadata exposed as WASI environment variables.
+    let input_json = serde_json::to_string(input).unwrap_or_else(|_| "{}".to_string());
+    let function_name_owned = function_name.to_string();
+    let wasi_envs = vec![
+        format!("FUNCTION_NAME={}", function_name_owned),
+        format!("CONTENT_LENGTH={}", input_json.len()),
+        "CONTENT_TYPE=application/json".to_string(),
+    ];
 
-    // Execute function
-    match vm.run_func(function_name, params![]) {
-        Ok(results) => {
-            // Convert results to JSON
-            let output: serde_json::Value = if results.is_empty() {
-                req.input.clone()
+    // Run the function on a blocking task so a hung or slow module can never
+    // block the actix worker. `run_func_with_timeout` (rather than racing
+    // `run_func` against `tokio::time::timeout`) is what actually bounds the
+    // module itself: a plain `tokio::time::timeout` only stops the handler
+    // from *waiting* on the blocking task, it never interrupts the OS
+    // thread, so a genuinely hung module (`loop {}`) would keep running
+    // forever and eventually exhaust the blocking pool.
+    let deadline = Duration::from_millis(timeout_ms);
+    let run_result = tokio::task::spawn_blocking(move || {
+        wagi::with_captured_stdio(input_json.as_bytes(), move || {
+            if let Some(wasi) = vm.wasi_module_mut() {
+                let env_refs: Vec<&str> = wasi_envs.iter().map(String::as_str).collect();
+                wasi.initialize(None, Some(env_refs), None);
+            }
+            let result = vm.run_func_with_timeout(None, &function_name_owned, params![], deadline);
+            let stats = collect_stats(&vm);
+            (result, stats)
+        })
+    })
+    .await;
+
+    let execution_time = start_time.elapsed().as_millis() as u64;
+
+    match run_result {
+        Err(join_err) => HttpResponse::InternalServerError().json(ExecuteResponse::error(execution_time, format!("Execution task failed: {}", join_err))),
+        Ok(Err(io_err)) => HttpResponse::InternalServerError().json(ExecuteResponse::error(execution_time, format!("Failed to wire WASI stdio: {}", io_err))),
+        Ok(Ok(wagi::StdioResult {
+            inner: (Ok(_results), stats),
+            stdout,
+        })) => HttpResponse::Ok().json(ExecuteResponse {
+            success: true,
+            output: Some(wagi::parse_module_output(&stdout)),
+            error: None,
+            execution_time: Some(execution_time),
+            memory_used: stats.memory_bytes,
+            instructions_executed: stats.instructions_executed,
+            cost: stats.cost,
+        }),
+        Ok(Ok(wagi::StdioResult {
+            inner: (Err(e), stats),
+            ..
+        })) => {
+            let message = e.to_string();
+            let error = match gas_limit {
+                Some(limit) if message.to_lowercase().contains("cost limit") => {
+                    format!("gas exhausted: execution exceeded cost limit of {}", limit)
+                }
+                _ if message.to_lowercase().contains("timeout") => {
+                    format!("timeout after {} ms", timeout_ms)
+                }
+                _ => format!("Execution error: {}", message),
+            };
+            let status = if message.to_lowercase().contains("timeout") {
+                HttpResponse::RequestTimeout()
             } else {
-                // Try to convert first result to JSON
-                // This is a simplified conversion - actual implementation may need more complex handling
-                serde_json::json!({ "result": "execution successful" })
+                HttpResponse::InternalServerError()
             };
+            status.json(ExecuteResponse {
+                success: false,
+                output: None,
+                error: Some(error),
+                execution_time: Some(execution_time),
+                memory_used: stats.memory_bytes,
+                instructions_executed: stats.instructions_executed,
+                cost: stats.cost,
+            })
+        }
+    }
+}
 
-            let execution_time = start_time.elapsed().as_millis() as u64;
+#[derive(Deserialize)]
+struct RunQuery {
+    version: Option<String>,
+}
 
-            Ok(HttpResponse::Ok().json(ExecuteResponse {
-                success: true,
-                output: Some(output),
-                error: None,
-                execution_time: Some(execution_time),
-                memory_used: None, // WasmEdge doesn't easily expose memory usage
-            }))
+/// `POST /modules` — registers a TOML manifest describing a named,
+/// versioned job. The binary is either supplied inline as base64 (`wasm`)
+/// or referenced by the hash of a module uploaded previously (`wasm_sha256`).
+async fn register_module(
+    body: web::Bytes,
+    cache: web::Data<ModuleCache>,
+    registry: web::Data<ModuleRegistry>,
+) -> ActixResult<HttpResponse> {
+    let body_str = match std::str::from_utf8(&body) {
+        Ok(s) => s,
+        Err(e) => {
+            return Ok(HttpResponse::BadRequest()
+                .json(serde_json::json!({ "error": format!("manifest body is not valid UTF-8: {}", e) })));
         }
+    };
+
+    let manifest: ManifestUpload = match toml::from_str(body_str) {
+        Ok(manifest) => manifest,
         Err(e) => {
-            let execution_time = start_time.elapsed().as_millis() as u64;
+            return Ok(HttpResponse::BadRequest()
+                .json(serde_json::json!({ "error": format!("invalid TOML manifest: {}", e) })));
+        }
+    };
 
-            Ok(HttpResponse::InternalServerError().json(ExecuteResponse {
-                success: false,
-                output: None,
-                error: Some(format!("Execution error: {}", e)),
-                execution_time: Some(execution_time),
-                memory_used: None,
-            }))
+    let hash = match (&manifest.wasm, &manifest.wasm_sha256) {
+        (Some(b64), _) => {
+            let bytes = match base64::decode(b64) {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    return Ok(HttpResponse::BadRequest()
+                        .json(serde_json::json!({ "error": format!("invalid WASM base64: {}", e) })));
+                }
+            };
+            let hash = ModuleCache::hash(&bytes);
+            if cache.get(&hash).is_none() {
+                let config = match build_config(DEFAULT_MEMORY_LIMIT_BYTES) {
+                    Ok(config) => config,
+                    Err(e) => {
+                        return Ok(HttpResponse::InternalServerError().json(serde_json::json!({ "error": e })));
+                    }
+                };
+                let compiled = match Module::from_bytes(Some(&config), &bytes) {
+                    Ok(module) => module,
+                    Err(e) => {
+                        return Ok(HttpResponse::BadRequest().json(
+                            serde_json::json!({ "error": format!("Failed to load/validate WASM: {}", e) }),
+                        ));
+                    }
+                };
+                cache.insert(
+                    hash,
+                    CachedModule {
+                        module: Arc::new(compiled),
+                    },
+                );
+            }
+            hash
         }
-    }
+        (None, Some(hex)) => match cache::parse_hash_hex(hex) {
+            Some(hash) if cache.get(&hash).is_some() => hash,
+            Some(_) => {
+                return Ok(HttpResponse::BadRequest().json(
+                    serde_json::json!({ "error": format!("no module previously uploaded for hash {}", hex) }),
+                ));
+            }
+            None => {
+                return Ok(HttpResponse::BadRequest().json(
+                    serde_json::json!({ "error": "wasm_sha256 must be a 64-character hex SHA-256 digest" }),
+                ));
+            }
+        },
+        (None, None) => {
+            return Ok(HttpResponse::BadRequest()
+                .json(serde_json::json!({ "error": "manifest must set `wasm` or `wasm_sha256`" })));
+        }
+    };
+
+    registry.register(
+        &manifest.namespace,
+        &manifest.name,
+        &manifest.version,
+        ManifestEntry {
+            description: manifest.description,
+            function_name: manifest.function_name,
+            blob_hash: cache::hash_to_hex(&hash),
+        },
+    );
+
+    Ok(HttpResponse::Created().json(serde_json::json!({
+        "namespace": manifest.namespace,
+        "name": manifest.name,
+        "version": manifest.version,
+        "wasm_sha256": cache::hash_to_hex(&hash),
+    })))
+}
+
+/// `GET /modules` — lists registered manifests and their versions.
+async fn list_modules(registry: web::Data<ModuleRegistry>) -> ActixResult<HttpResponse> {
+    Ok(HttpResponse::Ok().json(registry.list()))
+}
+
+/// `POST /run/{namespace}.{name}` — looks up the manifest (optionally
+/// pinned via `?version=`), resolves the binary, and executes it through
+/// the same path as `/execute`.
+async fn run_registered(
+    path: web::Path<(String, String)>,
+    query: web::Query<RunQuery>,
+    req: web::Json<ExecuteRequest>,
+    cache: web::Data<ModuleCache>,
+    registry: web::Data<ModuleRegistry>,
+) -> ActixResult<HttpResponse> {
+    let start_time = Instant::now();
+    let (namespace, name) = path.into_inner();
+
+    let entry = match registry.resolve(&namespace, &name, query.version.as_deref()) {
+        Some(entry) => entry,
+        None => {
+            return Ok(HttpResponse::NotFound().json(ExecuteResponse::error(start_time.elapsed().as_millis() as u64, format!(
+                    "no manifest registered for {}.{}",
+                    namespace, name
+                ))));
+        }
+    };
+
+    let hash = match cache::parse_hash_hex(&entry.blob_hash) {
+        Some(hash) => hash,
+        None => {
+            return Ok(HttpResponse::InternalServerError().json(ExecuteResponse::error(start_time.elapsed().as_millis() as u64, "manifest references a malformed blob hash")));
+        }
+    };
+
+    let function_name = req
+        .function_name
+        .as_deref()
+        .or(entry.function_name.as_deref())
+        .unwrap_or("main");
+    let memory_limit = req.memory_limit.unwrap_or(DEFAULT_MEMORY_LIMIT_BYTES);
+    let timeout_ms = req.timeout.unwrap_or(DEFAULT_TIMEOUT_MS);
+
+    let use_wasi_nn = match resolve_wasi_nn_backend(&req, start_time) {
+        Ok(flag) => flag,
+        Err(response) => return Ok(response),
+    };
+
+    Ok(run_module(
+        &cache,
+        hash,
+        None,
+        function_name,
+        &req.input,
+        memory_limit,
+        timeout_ms,
+        req.gas_limit,
+        use_wasi_nn,
+        start_time,
+    )
+    .await)
 }
 
 async fn health_check() -> ActixResult<HttpResponse> {
@@ -164,10 +569,18 @@ async fn main() -> std::io::Result<()> {
 
     println!("Starting WasmEdge HTTP Service on {}", bind_address);
 
-    HttpServer::new(|| {
+    let module_cache = web::Data::new(ModuleCache::new());
+    let module_registry = web::Data::new(ModuleRegistry::new());
+
+    HttpServer::new(move || {
         App::new()
+            .app_data(module_cache.clone())
+            .app_data(module_registry.clone())
             .route("/health", web::get().to(health_check))
             .route("/execute", web::post().to(execute_wasm))
+            .route("/modules", web::post().to(register_module))
+            .route("/modules", web::get().to(list_modules))
+            .route("/run/{namespace}.{name}", web::post().to(run_registered))
     })
     .bind(&bind_address)?
     .run()
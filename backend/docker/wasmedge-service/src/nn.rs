@@ -0,0 +1,30 @@
+// Opt-in WASI-NN backend.
+//
+// A `backend: "wasi-nn"` request doesn't change the execution contract at
+// all: the uploaded module still reads its `input` from stdin and writes
+// its result to stdout, same as any other WAGI module (see `wagi.rs`). All
+// this enables is the `wasi_nn` plugin on the VM, via
+// `VmBuilder::with_plugin_wasi_nn()`, so a module that imports the wasi-nn
+// host functions (load graph, set input tensor, compute, get output
+// tensor) can actually resolve those imports at instantiation time.
+//
+// WasmEdge has no Rust-level API for preloading a named graph from a spec
+// string — that's a `--nn-preload` CLI/runtime concept, not something
+// `Config` exposes. Any model bytes/URIs or tensors a module needs are the
+// caller's responsibility to ship inside `input`, exactly like any other
+// WAGI payload.
+
+use wasmedge_sdk::config::Config;
+use wasmedge_sdk::{Vm, VmBuilder};
+
+/// Builds a `Vm` with the `wasi_nn` plugin registered, for requests that set
+/// `backend: "wasi-nn"`. The returned `Vm` has no module registered yet —
+/// `run_module` loads the request's module into it via the same
+/// `Vm::register_module` call it uses for the default backend.
+pub fn build_vm(config: Config) -> Result<Vm, String> {
+    VmBuilder::new()
+        .with_config(config)
+        .with_plugin_wasi_nn()
+        .build()
+        .map_err(|e| format!("failed to build wasi-nn VM: {}", e))
+}
@@ -0,0 +1,153 @@
+// TOML manifest registry for named, versioned WASM jobs.
+//
+// A manifest gives a job a stable identifier (`namespace.name`) and maps it
+// to a version -> blob hash, where the blob itself lives in the
+// content-addressable `ModuleCache`. The indirection lets the binary behind
+// a job change (new version) without callers needing to track raw hashes.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+#[derive(Deserialize)]
+pub struct ManifestUpload {
+    pub name: String,
+    pub namespace: String,
+    pub version: String,
+    pub description: Option<String>,
+    pub function_name: Option<String>,
+    /// Base64-encoded WASM binary. Omit when `wasm_sha256` references a
+    /// module uploaded previously (e.g. via `/execute`).
+    pub wasm: Option<String>,
+    pub wasm_sha256: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct ManifestEntry {
+    pub description: Option<String>,
+    pub function_name: Option<String>,
+    pub blob_hash: String, // hex SHA-256, see `cache::hash_to_hex`
+}
+
+#[derive(Serialize)]
+pub struct ManifestSummary {
+    pub namespace: String,
+    pub name: String,
+    pub latest_version: String,
+    pub versions: Vec<String>,
+}
+
+struct NamespacedManifest {
+    versions: HashMap<String, ManifestEntry>,
+    latest_version: String,
+}
+
+#[derive(Default)]
+pub struct ModuleRegistry {
+    manifests: RwLock<HashMap<String, NamespacedManifest>>,
+}
+
+impl ModuleRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn key(namespace: &str, name: &str) -> String {
+        format!("{}.{}", namespace, name)
+    }
+
+    /// Registers a manifest version, making it the default (latest) version
+    /// for subsequent unversioned lookups.
+    pub fn register(&self, namespace: &str, name: &str, version: &str, entry: ManifestEntry) {
+        let key = Self::key(namespace, name);
+        let mut manifests = self.manifests.write().unwrap();
+        let namespaced = manifests.entry(key).or_insert_with(|| NamespacedManifest {
+            versions: HashMap::new(),
+            latest_version: version.to_string(),
+        });
+        namespaced.versions.insert(version.to_string(), entry);
+        namespaced.latest_version = version.to_string();
+    }
+
+    /// Resolves a manifest entry, defaulting to the most recently registered
+    /// version when `version` is not given.
+    pub fn resolve(&self, namespace: &str, name: &str, version: Option<&str>) -> Option<ManifestEntry> {
+        let key = Self::key(namespace, name);
+        let manifests = self.manifests.read().unwrap();
+        let namespaced = manifests.get(&key)?;
+        let version = version.unwrap_or(&namespaced.latest_version);
+        namespaced.versions.get(version).cloned()
+    }
+
+    pub fn list(&self) -> Vec<ManifestSummary> {
+        let manifests = self.manifests.read().unwrap();
+        manifests
+            .iter()
+            .map(|(key, namespaced)| {
+                let (namespace, name) = key.split_once('.').unwrap_or((key.as_str(), ""));
+                ManifestSummary {
+                    namespace: namespace.to_string(),
+                    name: name.to_string(),
+                    latest_version: namespaced.latest_version.clone(),
+                    versions: namespaced.versions.keys().cloned().collect(),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(blob_hash: &str) -> ManifestEntry {
+        ManifestEntry {
+            description: None,
+            function_name: None,
+            blob_hash: blob_hash.to_string(),
+        }
+    }
+
+    #[test]
+    fn resolve_defaults_to_most_recently_registered_version() {
+        let registry = ModuleRegistry::new();
+        registry.register("ns", "job", "1.0.0", entry("aaa"));
+        registry.register("ns", "job", "2.0.0", entry("bbb"));
+
+        let resolved = registry.resolve("ns", "job", None).unwrap();
+        assert_eq!(resolved.blob_hash, "bbb");
+    }
+
+    #[test]
+    fn resolve_can_pin_an_older_version() {
+        let registry = ModuleRegistry::new();
+        registry.register("ns", "job", "1.0.0", entry("aaa"));
+        registry.register("ns", "job", "2.0.0", entry("bbb"));
+
+        let resolved = registry.resolve("ns", "job", Some("1.0.0")).unwrap();
+        assert_eq!(resolved.blob_hash, "aaa");
+    }
+
+    #[test]
+    fn resolve_returns_none_for_unknown_manifest_or_version() {
+        let registry = ModuleRegistry::new();
+        registry.register("ns", "job", "1.0.0", entry("aaa"));
+
+        assert!(registry.resolve("ns", "other", None).is_none());
+        assert!(registry.resolve("ns", "job", Some("9.9.9")).is_none());
+    }
+
+    #[test]
+    fn list_reports_every_registered_version() {
+        let registry = ModuleRegistry::new();
+        registry.register("ns", "job", "1.0.0", entry("aaa"));
+        registry.register("ns", "job", "2.0.0", entry("bbb"));
+
+        let summaries = registry.list();
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].latest_version, "2.0.0");
+        let mut versions = summaries[0].versions.clone();
+        versions.sort();
+        assert_eq!(versions, vec!["1.0.0".to_string(), "2.0.0".to_string()]);
+    }
+}
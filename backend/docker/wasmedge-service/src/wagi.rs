@@ -0,0 +1,144 @@
+// WAGI-style stdio plumbing: the request's `input` is written to the
+// module's stdin and its stdout is captured into a buffer, mirroring the
+// CGI convention most small WASI functions are already written against.
+//
+// WasmEdge's WASI implementation talks to the process's real stdio file
+// descriptors rather than an in-memory stream, so for the duration of a
+// call we redirect fd 0/1 through a pair of pipes. That redirection is a
+// genuinely global resource, so calls are serialized on `STDIO_LOCK`
+// instead of per-request. That reintroduces a single-request bottleneck for
+// any WASI-backed call (`/execute` and friends still run one at a time
+// irrespective of the module cache), so lock contention is logged rather
+// than left silent — look for "waited ... for STDIO_LOCK" in the service
+// logs if WASI-backed throughput seems low under concurrent traffic.
+
+use std::io::{self, Read, Write};
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+static STDIO_LOCK: Mutex<()> = Mutex::new(());
+
+pub struct StdioResult<T> {
+    pub inner: T,
+    pub stdout: Vec<u8>,
+}
+
+/// Runs `f` with its stdin fed from `input` and its stdout captured.
+pub fn with_captured_stdio<T>(input: &[u8], f: impl FnOnce() -> T) -> io::Result<StdioResult<T>> {
+    let wait_start = Instant::now();
+    let _guard = STDIO_LOCK.lock().unwrap();
+    let waited = wait_start.elapsed();
+    if waited > Duration::from_millis(10) {
+        eprintln!(
+            "wagi: waited {:?} for STDIO_LOCK (WASI stdio redirection serializes on one \
+             process-wide lock; concurrent WASI-backed requests queue here)",
+            waited
+        );
+    }
+
+    let (stdin_read, mut stdin_write) = os_pipe::pipe()?;
+    let (mut stdout_read, stdout_write) = os_pipe::pipe()?;
+
+    let saved_stdin = dup_fd(libc::STDIN_FILENO)?;
+    let saved_stdout = dup_fd(libc::STDOUT_FILENO)?;
+
+    dup2_fd(stdin_read.as_raw_fd(), libc::STDIN_FILENO)?;
+    dup2_fd(stdout_write.as_raw_fd(), libc::STDOUT_FILENO)?;
+    drop(stdin_read);
+    drop(stdout_write);
+
+    // Write stdin and drain stdout on their own threads, concurrently with
+    // `f()` rather than before/after it: both pipes' kernel buffers are
+    // bounded (64 KiB by default on Linux), and a module that writes more
+    // than that to stdout before anyone reads it (or that is fed more than
+    // that on stdin before it starts reading) would block forever inside
+    // `f()` on the other end of the pipe — taking the single process-wide
+    // `STDIO_LOCK` down with it, and defeating `run_func_with_timeout`'s
+    // deadline, since a module parked in a blocking syscall isn't executing
+    // WASM instructions for the timeout to interrupt.
+    let input = input.to_vec();
+    let writer = std::thread::spawn(move || {
+        let _ = stdin_write.write_all(&input);
+        // Dropping `stdin_write` here sends EOF on the module's stdin.
+    });
+    let reader = std::thread::spawn(move || {
+        let mut stdout = Vec::new();
+        let _ = stdout_read.read_to_end(&mut stdout);
+        stdout
+    });
+
+    let inner = f();
+
+    dup2_fd(saved_stdin, libc::STDIN_FILENO)?;
+    dup2_fd(saved_stdout, libc::STDOUT_FILENO)?;
+    unsafe {
+        libc::close(saved_stdin);
+        libc::close(saved_stdout);
+    }
+
+    // `writer` is joined only once `STDIN_FILENO` is restored: that closes
+    // the last read-end fd, so a writer still blocked on input the module
+    // never read gets EPIPE and returns instead of hanging here.
+    writer.join().expect("stdin writer thread panicked");
+
+    // `reader` only finishes once its end of the pipe sees EOF, which
+    // happens above once the redirected `STDOUT_FILENO` is restored and the
+    // last write-end fd closes.
+    let stdout = reader.join().expect("stdout reader thread panicked");
+
+    Ok(StdioResult { inner, stdout })
+}
+
+fn dup_fd(fd: RawFd) -> io::Result<RawFd> {
+    let dup = unsafe { libc::dup(fd) };
+    if dup < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(dup)
+    }
+}
+
+fn dup2_fd(src: RawFd, dst: RawFd) -> io::Result<()> {
+    if unsafe { libc::dup2(src, dst) } < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+/// Parses captured stdout as JSON, falling back to a UTF-8 string and
+/// finally to a base64 payload when the bytes aren't valid JSON or UTF-8.
+pub fn parse_module_output(stdout: &[u8]) -> serde_json::Value {
+    if let Ok(value) = serde_json::from_slice::<serde_json::Value>(stdout) {
+        return value;
+    }
+    match std::str::from_utf8(stdout) {
+        Ok(text) => serde_json::Value::String(text.to_string()),
+        Err(_) => serde_json::json!({ "base64": base64::encode(stdout) }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_module_output_prefers_json() {
+        let value = parse_module_output(br#"{"ok":true}"#);
+        assert_eq!(value, serde_json::json!({ "ok": true }));
+    }
+
+    #[test]
+    fn parse_module_output_falls_back_to_utf8_string() {
+        let value = parse_module_output(b"not json");
+        assert_eq!(value, serde_json::Value::String("not json".to_string()));
+    }
+
+    #[test]
+    fn parse_module_output_falls_back_to_base64_for_invalid_utf8() {
+        let bytes = [0xff, 0xfe, 0xfd];
+        let value = parse_module_output(&bytes);
+        assert_eq!(value, serde_json::json!({ "base64": base64::encode(bytes) }));
+    }
+}